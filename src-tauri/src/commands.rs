@@ -1,7 +1,8 @@
 use serde::Serialize;
 use tauri::State;
 
-use crate::downloader::{self, ProcessHandle};
+use crate::downloader::{self, DownloadManagerHandle, JobId, JobState, ProcessHandle};
+use crate::metadata::{self, Metadata};
 use crate::settings::Settings;
 use crate::updater;
 use crate::utils;
@@ -12,6 +13,7 @@ use crate::utils;
 pub struct DepsStatus {
     pub ytdlp: bool,
     pub ffmpeg: bool,
+    pub spotdl: bool,
 }
 
 #[tauri::command]
@@ -19,6 +21,7 @@ pub fn check_dependencies() -> DepsStatus {
     DepsStatus {
         ytdlp: utils::is_ytdlp_installed(),
         ffmpeg: utils::is_ffmpeg_installed(),
+        spotdl: utils::is_spotdl_installed(),
     }
 }
 
@@ -30,10 +33,21 @@ pub fn get_settings() -> Settings {
 }
 
 #[tauri::command]
-pub fn save_settings(settings: Settings) -> Result<(), String> {
+pub fn save_settings(
+    manager: State<'_, DownloadManagerHandle>,
+    settings: Settings,
+) -> Result<(), String> {
+    manager.inner().set_max_concurrent(settings.max_concurrent_downloads);
     settings.save()
 }
 
+// ── Metadata ──────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn fetch_metadata(url: String) -> Result<Metadata, String> {
+    metadata::fetch_metadata(&url).await
+}
+
 // ── Download ──────────────────────────────────────────────────────────
 
 #[tauri::command]
@@ -53,6 +67,33 @@ pub async fn cancel_download(handle: State<'_, ProcessHandle>) -> Result<(), Str
     downloader::cancel(handle.inner().clone()).await
 }
 
+// ── Download queue ───────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn enqueue_download(
+    app: tauri::AppHandle,
+    manager: State<'_, DownloadManagerHandle>,
+    url: String,
+    audio_format: String,
+    playlist: bool,
+    save_path: String,
+) -> Result<JobId, String> {
+    Ok(manager
+        .inner()
+        .enqueue(app, url, audio_format, playlist, save_path)
+        .await)
+}
+
+#[tauri::command]
+pub async fn cancel_job(manager: State<'_, DownloadManagerHandle>, id: JobId) -> Result<(), String> {
+    manager.inner().cancel(id).await
+}
+
+#[tauri::command]
+pub async fn list_jobs(manager: State<'_, DownloadManagerHandle>) -> Result<Vec<JobState>, String> {
+    Ok(manager.inner().list().await)
+}
+
 // ── Updater ───────────────────────────────────────────────────────────
 
 #[derive(Debug, Serialize)]
@@ -1,32 +1,30 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
+
+use serde::Serialize;
 use tauri::{AppHandle, Emitter};
 use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::Command;
-use tokio::sync::Mutex;
+use tokio::process::{Child, Command};
+use tokio::sync::{Mutex, Semaphore};
 
+use crate::settings::Settings;
 use crate::utils;
 
 /// Shared handle so we can cancel the running process from another command.
-pub type ProcessHandle = Arc<Mutex<Option<tokio::process::Child>>>;
+pub type ProcessHandle = Arc<Mutex<Option<Child>>>;
 
 pub fn new_process_handle() -> ProcessHandle {
     Arc::new(Mutex::new(None))
 }
 
-/// Spawn yt-dlp and stream its output to the frontend via Tauri events.
-pub async fn run(
-    app: AppHandle,
-    handle: ProcessHandle,
-    url: String,
-    audio_format: String,
+fn build_args(
+    audio_format: &str,
     playlist: bool,
-    save_path: String,
-) -> Result<(), String> {
-    let ytdlp = utils::ytdlp_path();
-    if !ytdlp.is_file() {
-        return Err("yt-dlp.exe not found. Use Check Update to download it.".into());
-    }
-
+    save_path: &str,
+    url: &str,
+    settings: &Settings,
+) -> Vec<String> {
     let bin_dir = utils::bin_dir();
 
     let mut args: Vec<String> = vec![
@@ -43,7 +41,7 @@ pub async fn run(
 
     if audio_format != "best" {
         args.push("--audio-format".into());
-        args.push(audio_format);
+        args.push(audio_format.to_string());
     }
 
     if playlist {
@@ -52,15 +50,166 @@ pub async fn run(
         args.push("--no-playlist".into());
     }
 
-    args.push(url);
+    let pp = &settings.post_processing;
+    if pp.embed_thumbnail {
+        args.push("--embed-thumbnail".into());
+    }
+    if pp.embed_metadata {
+        args.push("--embed-metadata".into());
+        args.push("--embed-chapters".into());
+    }
+    if pp.sponsorblock_remove {
+        args.push("--sponsorblock-remove".into());
+        args.push("all".into());
+    }
+    if let Some(quality) = pp.audio_quality.as_ref().filter(|q| !q.trim().is_empty()) {
+        args.push("--audio-quality".into());
+        args.push(quality.trim().to_string());
+    }
+
+    for extra in &settings.extra_args {
+        let trimmed = extra.trim();
+        if !trimmed.is_empty() {
+            args.push(trimmed.to_string());
+        }
+    }
+
+    args.push(url.to_string());
+    args
+}
+
+/// Apply `settings.working_directory` to a spawn, if set.
+fn apply_working_dir(cmd: &mut Command, settings: &Settings) {
+    if let Some(dir) = settings
+        .working_directory
+        .as_ref()
+        .filter(|d| !d.trim().is_empty())
+    {
+        cmd.current_dir(dir);
+    }
+}
+
+/// Spotify links need spotDL; yt-dlp can't resolve them. Everything else
+/// (YouTube, SoundCloud, ...) keeps going through yt-dlp.
+fn is_spotify_url(url: &str) -> bool {
+    url.contains("open.spotify.com") || url.contains("://spotify.com") || url.contains("://www.spotify.com")
+}
+
+fn build_spotdl_args(audio_format: &str, save_path: &str, url: &str, settings: &Settings) -> Vec<String> {
+    let ffmpeg = utils::bin_dir().join(utils::FFMPEG_BINARY_NAME);
+
+    let mut args: Vec<String> = vec![
+        "download".into(),
+        url.to_string(),
+        "--output".into(),
+        format!("{save_path}/{{title}}.{{output-ext}}"),
+        "--ffmpeg".into(),
+        ffmpeg.to_string_lossy().to_string(),
+    ];
+
+    if audio_format != "best" {
+        args.push("--format".into());
+        args.push(audio_format.to_string());
+    }
+
+    for extra in &settings.extra_args {
+        let trimmed = extra.trim();
+        if !trimmed.is_empty() {
+            args.push(trimmed.to_string());
+        }
+    }
+
+    args
+}
+
+/// Which yt-dlp-alike process is producing a job's output; the two backends
+/// report progress in different formats, so callers need this to pick the
+/// right parser in [`parse_progress_line`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Backend {
+    YtDlp,
+    SpotDl,
+}
+
+/// Parse one line of a child process's output into progress, using the
+/// format appropriate to whichever backend produced it.
+fn parse_progress_line(backend: Backend, line: &str) -> Option<utils::DownloadProgress> {
+    match backend {
+        Backend::YtDlp => utils::parse_progress(line),
+        Backend::SpotDl => utils::parse_spotdl_progress(line),
+    }
+}
+
+/// Pick which backend handles `url` and build its binary path + argv:
+/// spotDL for Spotify links, yt-dlp for everything else.
+fn resolve_backend(
+    audio_format: &str,
+    playlist: bool,
+    save_path: &str,
+    url: &str,
+    settings: &Settings,
+) -> Result<(Backend, std::path::PathBuf, Vec<String>), String> {
+    if is_spotify_url(url) {
+        let spotdl = utils::spotdl_path();
+        if !spotdl.is_file() && !utils::is_spotdl_installed() {
+            return Err(format!(
+                "{} not found. Install spotDL to download from Spotify.",
+                utils::SPOTDL_BINARY_NAME
+            ));
+        }
+        let bin = if spotdl.is_file() {
+            spotdl
+        } else {
+            std::path::PathBuf::from(utils::SPOTDL_BINARY_NAME)
+        };
+        Ok((
+            Backend::SpotDl,
+            bin,
+            build_spotdl_args(audio_format, save_path, url, settings),
+        ))
+    } else {
+        let ytdlp = utils::ytdlp_path();
+        if !ytdlp.is_file() {
+            return Err(format!(
+                "{} not found. Use Check Update to download it.",
+                utils::BINARY_NAME
+            ));
+        }
+        Ok((
+            Backend::YtDlp,
+            ytdlp,
+            build_args(audio_format, playlist, save_path, url, settings),
+        ))
+    }
+}
+
+/// Spawn the download backend (yt-dlp, or spotDL for Spotify links) and
+/// stream its output to the frontend via Tauri events.
+///
+/// Single-job convenience path kept for backward compatibility; new code
+/// that needs more than one simultaneous transfer should go through
+/// [`DownloadManager::enqueue`] instead.
+pub async fn run(
+    app: AppHandle,
+    handle: ProcessHandle,
+    url: String,
+    audio_format: String,
+    playlist: bool,
+    save_path: String,
+) -> Result<(), String> {
+    let settings = Settings::load();
+    let (backend, bin_path, args) =
+        resolve_backend(&audio_format, playlist, &save_path, &url, &settings)?;
 
-    let mut child = Command::new(&ytdlp)
-        .args(&args)
+    let mut cmd = Command::new(&bin_path);
+    cmd.args(&args)
         .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped())
-        .creation_flags(0x08000000) // CREATE_NO_WINDOW
+        .stderr(std::process::Stdio::piped());
+    apply_working_dir(&mut cmd, &settings);
+    utils::prepare_tokio_child(&mut cmd);
+    let mut child = cmd
         .spawn()
-        .map_err(|e| format!("Failed to start yt-dlp: {e}"))?;
+        .map_err(|e| format!("Failed to start {}: {e}", bin_path.to_string_lossy()))?;
 
     let stdout = child
         .stdout
@@ -82,8 +231,8 @@ pub async fn run(
     let stdout_task = tokio::spawn(async move {
         let mut reader = BufReader::new(stdout).lines();
         while let Ok(Some(line)) = reader.next_line().await {
-            if let Some(pct) = utils::parse_progress(&line) {
-                let _ = app2.emit("download-progress", pct);
+            if let Some(progress) = parse_progress_line(backend, &line) {
+                let _ = app2.emit("download-progress", progress);
             }
             let _ = app2.emit("download-log", &line);
         }
@@ -137,3 +286,346 @@ pub async fn cancel(handle: ProcessHandle) -> Result<(), String> {
     *guard = None;
     Ok(())
 }
+
+// ── Job queue ─────────────────────────────────────────────────────────
+
+/// Identifies one queued/running download within a [`DownloadManager`].
+pub type JobId = u64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// Snapshot of a job's state, safe to hand to the frontend via `list_jobs`.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobState {
+    pub id: JobId,
+    pub url: String,
+    pub status: JobStatus,
+    pub progress: Option<utils::DownloadProgress>,
+}
+
+/// Per-job process slot, also used to make cancellation race-free: `cancel`
+/// and `spawn_and_stream` both resolve it under a single lock acquisition,
+/// so whichever one observes the other's write first "wins" rather than
+/// silently clobbering it.
+enum ChildSlot {
+    /// Spawn hasn't stored the `Child` yet.
+    Pending,
+    Running(Child),
+    /// Either a running child was killed, or `cancel` raced ahead of
+    /// `spawn_and_stream` — in the latter case the next write to this slot
+    /// must kill its `Child` on sight instead of storing it.
+    Cancelled,
+}
+
+struct JobEntry {
+    state: JobState,
+    /// Own lock per job so waiting on one job's process never blocks
+    /// bookkeeping (status updates, `list`, `cancel`) for the others.
+    child: Arc<Mutex<ChildSlot>>,
+}
+
+/// Tracks every queued, running, and finished download job behind a single
+/// lock, and bounds how many yt-dlp/spotDL processes run concurrently via a
+/// semaphore. Events are namespaced per job (`download-progress:{id}`,
+/// `download-log:{id}`, `download-complete:{id}`) so the UI can render
+/// several simultaneous transfers.
+pub struct DownloadManager {
+    jobs: Mutex<HashMap<JobId, JobEntry>>,
+    next_id: AtomicU64,
+    semaphore: Arc<Semaphore>,
+    /// Mirrors the semaphore's configured total so [`set_max_concurrent`]
+    /// can compute a delta; `Semaphore` itself only exposes the *available*
+    /// count, not the total it was built with.
+    ///
+    /// [`set_max_concurrent`]: DownloadManager::set_max_concurrent
+    max_permits: AtomicUsize,
+}
+
+pub type DownloadManagerHandle = Arc<DownloadManager>;
+
+/// Concurrency cap starts out from `Settings`; call
+/// [`DownloadManager::set_max_concurrent`] to apply a changed value without
+/// restarting the app.
+pub fn new_download_manager() -> DownloadManagerHandle {
+    let permits = Settings::load().max_concurrent_downloads.max(1);
+    Arc::new(DownloadManager {
+        jobs: Mutex::new(HashMap::new()),
+        next_id: AtomicU64::new(1),
+        semaphore: Arc::new(Semaphore::new(permits)),
+        max_permits: AtomicUsize::new(permits),
+    })
+}
+
+impl DownloadManager {
+    /// Queue a download and return its `JobId` immediately; the job runs in
+    /// the background once a concurrency slot is available.
+    pub async fn enqueue(
+        self: &Arc<Self>,
+        app: AppHandle,
+        url: String,
+        audio_format: String,
+        playlist: bool,
+        save_path: String,
+    ) -> JobId {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+
+        {
+            let mut jobs = self.jobs.lock().await;
+            jobs.insert(
+                id,
+                JobEntry {
+                    state: JobState {
+                        id,
+                        url: url.clone(),
+                        status: JobStatus::Queued,
+                        progress: None,
+                    },
+                    child: Arc::new(Mutex::new(ChildSlot::Pending)),
+                },
+            );
+        }
+
+        let manager = Arc::clone(self);
+        tokio::spawn(async move {
+            let _permit = manager
+                .semaphore
+                .acquire()
+                .await
+                .expect("semaphore is never closed");
+            manager.run_job(app, id, url, audio_format, playlist, save_path).await;
+        });
+
+        id
+    }
+
+    async fn set_status(&self, id: JobId, status: JobStatus) {
+        let mut jobs = self.jobs.lock().await;
+        if let Some(entry) = jobs.get_mut(&id) {
+            entry.state.status = status;
+        }
+    }
+
+    /// Transition a job from `Queued` to `Running`, unless it was cancelled
+    /// while still waiting on the semaphore. Returns `false` in that case so
+    /// the caller can skip spawning it entirely.
+    async fn try_start(&self, id: JobId) -> bool {
+        let mut jobs = self.jobs.lock().await;
+        match jobs.get_mut(&id) {
+            Some(entry) if entry.state.status == JobStatus::Cancelled => false,
+            Some(entry) => {
+                entry.state.status = JobStatus::Running;
+                true
+            }
+            None => false,
+        }
+    }
+
+    async fn run_job(
+        self: Arc<Self>,
+        app: AppHandle,
+        id: JobId,
+        url: String,
+        audio_format: String,
+        playlist: bool,
+        save_path: String,
+    ) {
+        if !self.try_start(id).await {
+            let _ = app.emit(&format!("download-complete:{id}"), "failed:cancelled".to_string());
+            return;
+        }
+
+        let result = self
+            .spawn_and_stream(&app, id, &url, &audio_format, playlist, &save_path)
+            .await;
+
+        let mut jobs = self.jobs.lock().await;
+        if let Some(entry) = jobs.get_mut(&id) {
+            entry.state.status = match &result {
+                Ok(()) => JobStatus::Completed,
+                Err(e) if e.as_str() == "cancelled" => JobStatus::Cancelled,
+                Err(_) => JobStatus::Failed,
+            };
+        }
+        drop(jobs);
+
+        let payload = match result {
+            Ok(()) => "success".to_string(),
+            Err(e) => format!("failed:{e}"),
+        };
+        let _ = app.emit(&format!("download-complete:{id}"), payload);
+    }
+
+    async fn spawn_and_stream(
+        &self,
+        app: &AppHandle,
+        id: JobId,
+        url: &str,
+        audio_format: &str,
+        playlist: bool,
+        save_path: &str,
+    ) -> Result<(), String> {
+        {
+            let jobs = self.jobs.lock().await;
+            if jobs.get(&id).map(|e| e.state.status) == Some(JobStatus::Cancelled) {
+                return Err("cancelled".into());
+            }
+        }
+
+        let settings = Settings::load();
+        let (backend, bin_path, args) =
+            resolve_backend(audio_format, playlist, save_path, url, &settings)?;
+
+        let mut cmd = Command::new(&bin_path);
+        cmd.args(&args)
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped());
+        apply_working_dir(&mut cmd, &settings);
+        utils::prepare_tokio_child(&mut cmd);
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| format!("Failed to start {}: {e}", bin_path.to_string_lossy()))?;
+
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or("Failed to capture yt-dlp stdout")?;
+        let stderr = child
+            .stderr
+            .take()
+            .ok_or("Failed to capture yt-dlp stderr")?;
+
+        let child_handle = {
+            let jobs = self.jobs.lock().await;
+            let entry = jobs.get(&id).ok_or("Job was cancelled before it started")?;
+            entry.child.clone()
+        };
+
+        // Check-and-store under one lock acquisition: if `cancel` raced
+        // ahead of us while we were still spawning, the slot is already
+        // `Cancelled` and there is no `Child` for it to have killed — so we
+        // kill this freshly spawned one ourselves before anyone else can
+        // observe it as "stored".
+        {
+            let mut slot = child_handle.lock().await;
+            match &*slot {
+                ChildSlot::Cancelled => {
+                    if let Some(pid) = child.id() {
+                        utils::kill_process_tree(pid);
+                    }
+                    return Err("cancelled".into());
+                }
+                _ => *slot = ChildSlot::Running(child),
+            }
+        }
+
+        let app2 = app.clone();
+        let jobs_for_progress = &self.jobs;
+        let progress_event = format!("download-progress:{id}");
+        let log_event = format!("download-log:{id}");
+        let stdout_task = async move {
+            let mut reader = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = reader.next_line().await {
+                if let Some(progress) = parse_progress_line(backend, &line) {
+                    let mut jobs = jobs_for_progress.lock().await;
+                    if let Some(entry) = jobs.get_mut(&id) {
+                        entry.state.progress = Some(progress.clone());
+                    }
+                    drop(jobs);
+                    let _ = app2.emit(&progress_event, progress);
+                }
+                let _ = app2.emit(&log_event, &line);
+            }
+        };
+
+        let app3 = app.clone();
+        let err_log_event = format!("download-log:{id}");
+        let stderr_task = async move {
+            let mut reader = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = reader.next_line().await {
+                let _ = app3.emit(&err_log_event, &line);
+            }
+        };
+
+        tokio::join!(stdout_task, stderr_task);
+
+        let status = {
+            let mut slot = child_handle.lock().await;
+            match &mut *slot {
+                ChildSlot::Running(child) => child.wait().await.map_err(|e| e.to_string())?,
+                _ => return Err("cancelled".into()),
+            }
+        };
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(status.code().unwrap_or(-1).to_string())
+        }
+    }
+
+    /// Kill a job's process tree and mark it cancelled. A no-op (status
+    /// left untouched) if the job already reached a terminal state — it
+    /// would be wrong to rewrite a finished job's real `Completed`/`Failed`
+    /// outcome to `Cancelled` after the fact.
+    pub async fn cancel(&self, id: JobId) -> Result<(), String> {
+        let child_handle = {
+            let mut jobs = self.jobs.lock().await;
+            let entry = jobs.get_mut(&id).ok_or("Job not found")?;
+            match entry.state.status {
+                JobStatus::Queued | JobStatus::Running => {
+                    entry.state.status = JobStatus::Cancelled;
+                    entry.child.clone()
+                }
+                JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled => {
+                    return Ok(());
+                }
+            }
+        };
+
+        // Same lock acquisition `spawn_and_stream` uses to store the child:
+        // whichever of us gets here first wins, and the loser sees our write.
+        let mut slot = child_handle.lock().await;
+        if let ChildSlot::Running(child) = std::mem::replace(&mut *slot, ChildSlot::Cancelled) {
+            if let Some(pid) = child.id() {
+                utils::kill_process_tree(pid);
+            }
+        }
+        Ok(())
+    }
+
+    /// Snapshot every tracked job (queued, running, and finished).
+    pub async fn list(&self) -> Vec<JobState> {
+        let jobs = self.jobs.lock().await;
+        jobs.values().map(|entry| entry.state.clone()).collect()
+    }
+
+    /// Apply an updated `max_concurrent_downloads` from `Settings` without
+    /// restarting the app. Raising the limit takes effect immediately, via
+    /// `Semaphore::add_permits`. Tokio's `Semaphore` has no safe way to
+    /// revoke permits that are already issued, so lowering the limit only
+    /// takes effect gradually, as running jobs finish and release theirs.
+    pub fn set_max_concurrent(&self, permits: usize) {
+        let permits = permits.max(1);
+        let previous = self.max_permits.swap(permits, Ordering::SeqCst);
+        if permits > previous {
+            self.semaphore.add_permits(permits - previous);
+        } else if permits < previous {
+            // Take the surplus out of circulation as running jobs hand it back.
+            let semaphore = Arc::clone(&self.semaphore);
+            let surplus = (previous - permits) as u32;
+            tauri::async_runtime::spawn(async move {
+                if let Ok(taken) = semaphore.acquire_many_owned(surplus).await {
+                    taken.forget();
+                }
+            });
+        }
+    }
+}
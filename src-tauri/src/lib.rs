@@ -1,5 +1,6 @@
 mod commands;
 mod downloader;
+mod metadata;
 mod settings;
 mod updater;
 mod utils;
@@ -10,12 +11,17 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
         .manage(downloader::new_process_handle())
+        .manage(downloader::new_download_manager())
         .invoke_handler(tauri::generate_handler![
             commands::check_dependencies,
             commands::get_settings,
             commands::save_settings,
+            commands::fetch_metadata,
             commands::start_download,
             commands::cancel_download,
+            commands::enqueue_download,
+            commands::cancel_job,
+            commands::list_jobs,
             commands::check_ytdlp_update,
             commands::update_ytdlp,
             commands::check_ffmpeg,
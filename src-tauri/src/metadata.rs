@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+use crate::utils;
+
+/// A single video's metadata as reported by yt-dlp.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct VideoMetadata {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub uploader: Option<String>,
+    #[serde(default)]
+    pub duration: Option<f64>,
+    #[serde(default)]
+    pub thumbnail: Option<String>,
+}
+
+/// A playlist's own metadata plus its flat entries (one per video, without
+/// each video's full details — that's what `--flat-playlist` buys us).
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PlaylistMetadata {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub uploader: Option<String>,
+    #[serde(default)]
+    pub entries: Vec<VideoMetadata>,
+}
+
+/// yt-dlp's `--dump-single-json` emits either a single video or a playlist,
+/// distinguished by the `_type` field. Mirrors `youtube_dl::YoutubeDlOutput`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind")]
+pub enum Metadata {
+    Video(VideoMetadata),
+    Playlist(PlaylistMetadata),
+}
+
+/// Untyped shape of yt-dlp's JSON output, used only to pick `Metadata::Video`
+/// vs `Metadata::Playlist` before re-deserializing into the typed variant.
+#[derive(Debug, Deserialize)]
+struct RawMetadata {
+    #[serde(rename = "_type", default)]
+    kind: Option<String>,
+    #[serde(flatten)]
+    value: serde_json::Value,
+}
+
+/// Run yt-dlp's metadata probe for `url` and parse the result into a typed
+/// [`Metadata`], without downloading anything.
+pub async fn fetch_metadata(url: &str) -> Result<Metadata, String> {
+    let ytdlp = utils::ytdlp_path();
+    if !ytdlp.is_file() {
+        return Err(format!(
+            "{} not found. Use Check Update to download it.",
+            utils::BINARY_NAME
+        ));
+    }
+
+    let mut cmd = Command::new(&ytdlp);
+    cmd.args([
+        "--dump-single-json",
+        "--flat-playlist",
+        "--no-download",
+        url,
+    ])
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped());
+    utils::prepare_tokio_child(&mut cmd);
+
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| format!("Failed to start yt-dlp: {e}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "yt-dlp metadata probe failed: {}",
+            stderr.trim()
+        ));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json = stdout.trim();
+    if json.is_empty() {
+        return Err("yt-dlp produced no JSON output".into());
+    }
+
+    let raw: RawMetadata =
+        serde_json::from_str(json).map_err(|e| format!("Failed to parse yt-dlp JSON: {e}"))?;
+
+    let metadata = if raw.kind.as_deref() == Some("playlist") {
+        let playlist: PlaylistMetadata = serde_json::from_value(raw.value)
+            .map_err(|e| format!("Failed to parse playlist metadata: {e}"))?;
+        Metadata::Playlist(playlist)
+    } else {
+        let video: VideoMetadata = serde_json::from_value(raw.value)
+            .map_err(|e| format!("Failed to parse video metadata: {e}"))?;
+        Metadata::Video(video)
+    };
+
+    Ok(metadata)
+}
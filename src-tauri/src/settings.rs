@@ -4,11 +4,41 @@ use std::path::PathBuf;
 
 use crate::utils;
 
+/// Toggles for yt-dlp's audio post-processing flags.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PostProcessing {
+    #[serde(default)]
+    pub embed_thumbnail: bool,
+    #[serde(default)]
+    pub embed_metadata: bool,
+    #[serde(default)]
+    pub sponsorblock_remove: bool,
+    #[serde(default)]
+    pub audio_quality: Option<String>,
+}
+
+fn default_max_concurrent_downloads() -> usize {
+    3
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
     pub save_path: String,
     pub audio_format: String,
     pub playlist_mode: bool,
+    /// Free-form extra arguments, appended after the managed flags of
+    /// whichever backend runs the download (yt-dlp, or spotDL for Spotify).
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+    /// Directory yt-dlp is run from, if set (useful for relative `--paths`
+    /// or config-file lookups); defaults to the app's own working directory.
+    #[serde(default)]
+    pub working_directory: Option<String>,
+    #[serde(default)]
+    pub post_processing: PostProcessing,
+    /// Max number of downloads the job queue runs at once.
+    #[serde(default = "default_max_concurrent_downloads")]
+    pub max_concurrent_downloads: usize,
 }
 
 impl Default for Settings {
@@ -21,6 +51,10 @@ impl Default for Settings {
             save_path: downloads.to_string_lossy().to_string(),
             audio_format: "best".to_string(),
             playlist_mode: false,
+            extra_args: Vec::new(),
+            working_directory: None,
+            post_processing: PostProcessing::default(),
+            max_concurrent_downloads: default_max_concurrent_downloads(),
         }
     }
 }
@@ -1,14 +1,119 @@
 use serde::Deserialize;
+use sha2::{Digest, Sha256};
 use std::fs;
 use std::io::Read;
-#[cfg(windows)]
-use std::os::windows::process::CommandExt;
 use tauri::{AppHandle, Emitter};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 
 use crate::utils;
 
+/// yt-dlp publishes this checksums file with every release.
+const YTDLP_CHECKSUMS_URL: &str =
+    "https://github.com/yt-dlp/yt-dlp/releases/latest/download/SHA2-256SUMS";
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
+
+/// Compare `bytes` against a published hex digest, case-insensitively.
+/// Returns the (lowercase) digest we computed on success, for logging.
+fn verify_sha256(bytes: &[u8], expected_hex: &str) -> Result<String, String> {
+    let actual = sha256_hex(bytes);
+    if actual.eq_ignore_ascii_case(expected_hex.trim()) {
+        Ok(actual)
+    } else {
+        Err(format!(
+            "checksum mismatch: expected {}, got {actual}",
+            expected_hex.trim()
+        ))
+    }
+}
+
+/// Fetch yt-dlp's `SHA2-256SUMS` release asset and pick out the hash for
+/// `asset_name` (each line is `<hex>  <filename>`, `sha256sum`-style).
+async fn fetch_ytdlp_checksum(client: &reqwest::Client, asset_name: &str) -> Result<String, String> {
+    let response = client
+        .get(YTDLP_CHECKSUMS_URL)
+        .send()
+        .await
+        .map_err(|e| format!("Checksum download failed: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!("Checksum download HTTP {}", response.status()));
+    }
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("Checksum read error: {e}"))?;
+
+    text.lines()
+        .find_map(|line| {
+            let mut parts = line.split_whitespace();
+            let hash = parts.next()?;
+            let name = parts.next()?.trim_start_matches('*');
+            (name == asset_name).then(|| hash.to_string())
+        })
+        .ok_or_else(|| format!("No checksum entry for {asset_name} in SHA2-256SUMS"))
+}
+
+/// Fetch a single-file checksum published alongside a download (the
+/// FFmpeg-Builds convention of a `<asset>.sha256` sibling asset).
+async fn fetch_sibling_checksum(client: &reqwest::Client, asset_url: &str) -> Result<String, String> {
+    let response = client
+        .get(format!("{asset_url}.sha256"))
+        .send()
+        .await
+        .map_err(|e| format!("Checksum download failed: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!("Checksum download HTTP {}", response.status()));
+    }
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("Checksum read error: {e}"))?;
+
+    text.split_whitespace()
+        .next()
+        .map(str::to_string)
+        .ok_or_else(|| "Checksum file was empty".to_string())
+}
+
+/// Aggregate checksums file FFmpeg-Builds also publishes per release,
+/// alongside the individual assets — used as a fallback for builds that
+/// don't ship a `.sha256` sidecar for every asset.
+const FFMPEG_CHECKSUMS_URL: &str =
+    "https://github.com/yt-dlp/FFmpeg-Builds/releases/download/latest/checksums.sha256";
+
+/// Fetch the checksum for `asset_url`'s file: try the `<asset>.sha256`
+/// sidecar first, then fall back to the release's aggregate checksums file
+/// (`sha256sum`-style: `<hex>  <filename>` per line) if no sidecar exists.
+async fn fetch_ffmpeg_checksum(client: &reqwest::Client, asset_url: &str) -> Result<String, String> {
+    if let Ok(hash) = fetch_sibling_checksum(client, asset_url).await {
+        return Ok(hash);
+    }
+
+    let asset_name = asset_url.rsplit('/').next().unwrap_or(asset_url);
+    let text = client
+        .get(FFMPEG_CHECKSUMS_URL)
+        .send()
+        .await
+        .map_err(|e| format!("Checksum download failed: {e}"))?
+        .text()
+        .await
+        .map_err(|e| format!("Checksum read error: {e}"))?;
+
+    text.lines()
+        .find_map(|line| {
+            let mut parts = line.split_whitespace();
+            let hash = parts.next()?;
+            let name = parts.next()?.trim_start_matches('*');
+            (name == asset_name).then(|| hash.to_string())
+        })
+        .ok_or_else(|| format!("No checksum entry for {asset_name}"))
+}
+
 #[derive(Debug, Deserialize)]
 struct GitHubRelease {
     tag_name: String,
@@ -35,9 +140,10 @@ pub async fn local_version() -> Result<String, String> {
         return Err("yt-dlp not installed".into());
     }
 
-    let output = Command::new(&ytdlp)
-        .arg("--version")
-        .creation_flags(0x08000000)
+    let mut cmd = Command::new(&ytdlp);
+    cmd.arg("--version");
+    utils::prepare_tokio_child(&mut cmd);
+    let output = cmd
         .output()
         .await
         .map_err(|e| format!("Failed to run yt-dlp: {e}"))?;
@@ -45,6 +151,17 @@ pub async fn local_version() -> Result<String, String> {
     Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
 
+/// Name of the yt-dlp release asset to download for this platform.
+fn ytdlp_asset_name() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "yt-dlp.exe"
+    } else if cfg!(target_os = "macos") {
+        "yt-dlp_macos"
+    } else {
+        "yt-dlp"
+    }
+}
+
 /// Check the latest yt-dlp release via GitHub API.
 pub async fn latest_release() -> Result<(String, String), String> {
     let client = http_client()?;
@@ -58,24 +175,25 @@ pub async fn latest_release() -> Result<(String, String), String> {
         .await
         .map_err(|e| format!("Parse error: {e}"))?;
 
+    let asset_name = ytdlp_asset_name();
     let asset = release
         .assets
         .iter()
-        .find(|a| a.name == "yt-dlp.exe")
-        .ok_or("yt-dlp.exe asset not found in release")?;
+        .find(|a| a.name == asset_name)
+        .ok_or_else(|| format!("{asset_name} asset not found in release"))?;
 
     Ok((release.tag_name, asset.browser_download_url.clone()))
 }
 
-/// Download the latest yt-dlp.exe from GitHub and replace the local binary atomically.
+/// Download the latest yt-dlp binary from GitHub and replace the local copy atomically.
 pub async fn download_ytdlp(app: &AppHandle, url: &str) -> Result<(), String> {
     let bin_dir = utils::bin_dir();
     fs::create_dir_all(&bin_dir).map_err(|e| format!("Cannot create bin dir: {e}"))?;
 
     let target = utils::ytdlp_path();
-    let tmp = bin_dir.join("yt-dlp.exe.tmp");
+    let tmp = bin_dir.join(format!("{}.tmp", utils::BINARY_NAME));
 
-    let _ = app.emit("update-log", "Downloading yt-dlp.exe...");
+    let _ = app.emit("update-log", format!("Downloading {}...", utils::BINARY_NAME));
 
     let client = http_client()?;
 
@@ -96,13 +214,64 @@ pub async fn download_ytdlp(app: &AppHandle, url: &str) -> Result<(), String> {
 
     fs::write(&tmp, &bytes).map_err(|e| format!("Write error: {e}"))?;
 
+    let _ = app.emit("update-log", "Verifying checksum...");
+    match verify_download(&client, &bytes, ytdlp_asset_name(), &tmp).await {
+        Ok(hash) => {
+            let _ = app.emit("update-log", format!("Verified SHA256: {hash}"));
+        }
+        Err(e) => {
+            let _ = app.emit("update-log", format!("Checksum verification failed: {e}"));
+            return Err(e);
+        }
+    }
+
     // Atomic replace: remove old, rename tmp.
     if target.is_file() {
         fs::remove_file(&target).map_err(|e| format!("Cannot remove old binary: {e}"))?;
     }
     fs::rename(&tmp, &target).map_err(|e| format!("Rename failed: {e}"))?;
+    mark_executable(&target)?;
+
+    let _ = app.emit(
+        "update-log",
+        format!("{} updated successfully.", utils::BINARY_NAME),
+    );
+    Ok(())
+}
+
+/// Verify `bytes` against yt-dlp's published `SHA2-256SUMS`, deleting `tmp`
+/// and returning an error on mismatch or if no checksum entry is published.
+/// On success, returns the verified hex digest for logging.
+async fn verify_download(
+    client: &reqwest::Client,
+    bytes: &[u8],
+    asset_name: &str,
+    tmp: &std::path::Path,
+) -> Result<String, String> {
+    let expected = fetch_ytdlp_checksum(client, asset_name).await.map_err(|e| {
+        let _ = fs::remove_file(tmp);
+        e
+    })?;
+    verify_sha256(bytes, &expected).map_err(|e| {
+        let _ = fs::remove_file(tmp);
+        e
+    })
+}
 
-    let _ = app.emit("update-log", "yt-dlp.exe updated successfully.");
+/// On Unix the downloaded binary doesn't carry the executable bit; set it.
+/// No-op on Windows, where executability isn't permission-based.
+#[cfg(unix)]
+fn mark_executable(path: &std::path::Path) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut perms = fs::metadata(path)
+        .map_err(|e| format!("Cannot stat {}: {e}", path.display()))?
+        .permissions();
+    perms.set_mode(perms.mode() | 0o755);
+    fs::set_permissions(path, perms).map_err(|e| format!("Cannot chmod {}: {e}", path.display()))
+}
+
+#[cfg(windows)]
+fn mark_executable(_path: &std::path::Path) -> Result<(), String> {
     Ok(())
 }
 
@@ -114,11 +283,12 @@ pub async fn self_update(app: AppHandle) -> Result<(), String> {
         return Err("yt-dlp not installed".into());
     }
 
-    let mut child = Command::new(&ytdlp)
-        .arg("-U")
+    let mut cmd = Command::new(&ytdlp);
+    cmd.arg("-U")
         .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped())
-        .creation_flags(0x08000000)
+        .stderr(std::process::Stdio::piped());
+    utils::prepare_tokio_child(&mut cmd);
+    let mut child = cmd
         .spawn()
         .map_err(|e| format!("Failed to start yt-dlp: {e}"))?;
 
@@ -145,26 +315,38 @@ pub async fn self_update(app: AppHandle) -> Result<(), String> {
 
 // ── FFmpeg ────────────────────────────────────────────────────────────
 
-const FFMPEG_ZIP_URL: &str =
-    "https://github.com/yt-dlp/FFmpeg-Builds/releases/download/latest/ffmpeg-master-latest-win64-gpl.zip";
+const FFMPEG_WIN64_URL: &str = "https://github.com/yt-dlp/FFmpeg-Builds/releases/download/latest/ffmpeg-master-latest-win64-gpl.zip";
+const FFMPEG_MACOS64_URL: &str = "https://github.com/yt-dlp/FFmpeg-Builds/releases/download/latest/ffmpeg-master-latest-macos64-gpl.zip";
+const FFMPEG_LINUX64_URL: &str = "https://github.com/yt-dlp/FFmpeg-Builds/releases/download/latest/ffmpeg-master-latest-linux64-gpl.tar.xz";
+
+/// The FFmpeg-Builds release asset to download for this platform. Windows
+/// and macOS ship zip archives; Linux ships a tar.xz.
+fn ffmpeg_archive_url() -> &'static str {
+    if cfg!(target_os = "windows") {
+        FFMPEG_WIN64_URL
+    } else if cfg!(target_os = "macos") {
+        FFMPEG_MACOS64_URL
+    } else {
+        FFMPEG_LINUX64_URL
+    }
+}
 
 /// Get the local ffmpeg version string (if installed in bin dir or on PATH).
 pub fn local_ffmpeg_version() -> Option<String> {
     // Prefer the bundled copy.
-    let bin = utils::bin_dir().join("ffmpeg.exe");
+    let bin = utils::bin_dir().join(utils::FFMPEG_BINARY_NAME);
     let exe = if bin.is_file() {
         bin.to_string_lossy().to_string()
     } else {
-        "ffmpeg".to_string()
+        utils::FFMPEG_BINARY_NAME.to_string()
     };
 
-    let output = std::process::Command::new(&exe)
-        .arg("-version")
+    let mut cmd = std::process::Command::new(&exe);
+    cmd.arg("-version")
         .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::null())
-        .creation_flags(0x08000000)
-        .output()
-        .ok()?;
+        .stderr(std::process::Stdio::null());
+    utils::prepare_child(&mut cmd);
+    let output = cmd.output().ok()?;
 
     let text = String::from_utf8_lossy(&output.stdout);
     // First line is like "ffmpeg version N-xxxxx-g... Copyright ..."
@@ -176,7 +358,76 @@ pub fn local_ffmpeg_version() -> Option<String> {
     Some(version.to_string())
 }
 
-/// Download ffmpeg from yt-dlp/FFmpeg-Builds, extract ffmpeg.exe + ffprobe.exe
+/// Write extracted archive bytes to `bin_dir/name`, replacing any existing
+/// file atomically via a `.tmp` write + rename.
+fn write_extracted(bin_dir: &std::path::Path, name: &str, buf: &[u8]) -> Result<(), String> {
+    let dest = bin_dir.join(name);
+    let tmp = bin_dir.join(format!("{name}.tmp"));
+    fs::write(&tmp, buf).map_err(|e| format!("Write error: {e}"))?;
+    if dest.is_file() {
+        fs::remove_file(&dest).map_err(|e| format!("Cannot remove old {name}: {e}"))?;
+    }
+    fs::rename(&tmp, &dest).map_err(|e| format!("Rename error: {e}"))?;
+    mark_executable(&dest)
+}
+
+/// Extract ffmpeg/ffprobe from a zip archive (Windows, macOS).
+/// The zip structure is: ffmpeg-master-latest-<platform>-gpl/bin/<binary>.
+fn extract_ffmpeg_zip(
+    bytes: &[u8],
+    bin_dir: &std::path::Path,
+    targets: &[&str],
+) -> Result<(), String> {
+    let cursor = std::io::Cursor::new(bytes);
+    let mut archive = zip::ZipArchive::new(cursor).map_err(|e| format!("Zip error: {e}"))?;
+
+    for i in 0..archive.len() {
+        let mut file = archive
+            .by_index(i)
+            .map_err(|e| format!("Zip entry error: {e}"))?;
+        let name = file.name().to_string();
+
+        for target in targets {
+            if name.ends_with(&format!("/bin/{target}")) || name == *target {
+                let mut buf = Vec::new();
+                file.read_to_end(&mut buf)
+                    .map_err(|e| format!("Extract error: {e}"))?;
+                write_extracted(bin_dir, target, &buf)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Extract ffmpeg/ffprobe from a tar.xz archive (Linux).
+/// The tar structure mirrors the zip: ffmpeg-master-latest-linux64-gpl/bin/<binary>.
+fn extract_ffmpeg_tar_xz(
+    bytes: &[u8],
+    bin_dir: &std::path::Path,
+    targets: &[&str],
+) -> Result<(), String> {
+    let decompressed = xz2::read::XzDecoder::new(bytes);
+    let mut archive = tar::Archive::new(decompressed);
+
+    for entry in archive.entries().map_err(|e| format!("Tar error: {e}"))? {
+        let mut entry = entry.map_err(|e| format!("Tar entry error: {e}"))?;
+        let path = entry.path().map_err(|e| format!("Tar path error: {e}"))?;
+        let name = path.to_string_lossy().to_string();
+
+        for target in targets {
+            if name.ends_with(&format!("/bin/{target}")) || name == *target {
+                let mut buf = Vec::new();
+                entry
+                    .read_to_end(&mut buf)
+                    .map_err(|e| format!("Extract error: {e}"))?;
+                write_extracted(bin_dir, target, &buf)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Download ffmpeg from yt-dlp/FFmpeg-Builds, extract ffmpeg + ffprobe
 /// into the app bin directory.
 pub async fn download_ffmpeg(app: &AppHandle) -> Result<(), String> {
     let bin_dir = utils::bin_dir();
@@ -187,7 +438,7 @@ pub async fn download_ffmpeg(app: &AppHandle) -> Result<(), String> {
     let client = http_client()?;
 
     let response = client
-        .get(FFMPEG_ZIP_URL)
+        .get(ffmpeg_archive_url())
         .send()
         .await
         .map_err(|e| format!("Download failed: {e}"))?;
@@ -201,44 +452,28 @@ pub async fn download_ffmpeg(app: &AppHandle) -> Result<(), String> {
         .await
         .map_err(|e| format!("Read error: {e}"))?;
 
-    let _ = app.emit("update-log", "Extracting ffmpeg...");
-
-    // Extract ffmpeg.exe and ffprobe.exe from the zip.
-    // The zip structure is: ffmpeg-master-latest-win64-gpl/bin/ffmpeg.exe
-    let cursor = std::io::Cursor::new(&bytes[..]);
-    let mut archive = zip::ZipArchive::new(cursor).map_err(|e| format!("Zip error: {e}"))?;
-
-    let targets = ["ffmpeg.exe", "ffprobe.exe"];
+    let _ = app.emit("update-log", "Verifying checksum...");
+    let expected = fetch_ffmpeg_checksum(&client, ffmpeg_archive_url()).await?;
+    let verified_hash = verify_sha256(&bytes, &expected)?;
+    let _ = app.emit("update-log", format!("Verified SHA256: {verified_hash}"));
 
-    for i in 0..archive.len() {
-        let mut file = archive.by_index(i).map_err(|e| format!("Zip entry error: {e}"))?;
-        let name = file.name().to_string();
+    let _ = app.emit("update-log", "Extracting ffmpeg...");
 
-        // Match files ending in /bin/ffmpeg.exe or /bin/ffprobe.exe
-        for target in &targets {
-            if name.ends_with(&format!("/bin/{target}")) || name == *target {
-                let dest = bin_dir.join(target);
-                let tmp = bin_dir.join(format!("{target}.tmp"));
-                let mut buf = Vec::new();
-                file.read_to_end(&mut buf)
-                    .map_err(|e| format!("Extract error: {e}"))?;
-                fs::write(&tmp, &buf).map_err(|e| format!("Write error: {e}"))?;
-                if dest.is_file() {
-                    fs::remove_file(&dest)
-                        .map_err(|e| format!("Cannot remove old {target}: {e}"))?;
-                }
-                fs::rename(&tmp, &dest).map_err(|e| format!("Rename error: {e}"))?;
-                let _ = app.emit("update-log", &format!("Extracted {target}"));
-            }
-        }
+    let targets = [utils::FFMPEG_BINARY_NAME, utils::FFPROBE_BINARY_NAME];
+    if cfg!(target_os = "linux") {
+        extract_ffmpeg_tar_xz(&bytes, &bin_dir, &targets)?;
+    } else {
+        extract_ffmpeg_zip(&bytes, &bin_dir, &targets)?;
+    }
+    for target in &targets {
+        let _ = app.emit("update-log", format!("Extracted {target}"));
     }
 
     // Verify both exist.
-    if !bin_dir.join("ffmpeg.exe").is_file() {
-        return Err("ffmpeg.exe not found in archive".into());
-    }
-    if !bin_dir.join("ffprobe.exe").is_file() {
-        return Err("ffprobe.exe not found in archive".into());
+    for target in &targets {
+        if !bin_dir.join(target).is_file() {
+            return Err(format!("{target} not found in archive"));
+        }
     }
 
     let _ = app.emit("update-log", "ffmpeg installed successfully.");
@@ -1,35 +1,61 @@
 use std::path::PathBuf;
 use std::process::Command;
 
-/// Returns `%LOCALAPPDATA%\SoundClip` (e.g. `C:\Users\<user>\AppData\Local\SoundClip`).
+/// Name of the yt-dlp binary on this platform.
+#[cfg(windows)]
+pub const BINARY_NAME: &str = "yt-dlp.exe";
+#[cfg(not(windows))]
+pub const BINARY_NAME: &str = "yt-dlp";
+
+/// Name of the ffmpeg binary on this platform.
+#[cfg(windows)]
+pub const FFMPEG_BINARY_NAME: &str = "ffmpeg.exe";
+#[cfg(not(windows))]
+pub const FFMPEG_BINARY_NAME: &str = "ffmpeg";
+
+/// Name of the ffprobe binary on this platform.
+#[cfg(windows)]
+pub const FFPROBE_BINARY_NAME: &str = "ffprobe.exe";
+#[cfg(not(windows))]
+pub const FFPROBE_BINARY_NAME: &str = "ffprobe";
+
+/// Name of the spotDL binary on this platform.
+#[cfg(windows)]
+pub const SPOTDL_BINARY_NAME: &str = "spotdl.exe";
+#[cfg(not(windows))]
+pub const SPOTDL_BINARY_NAME: &str = "spotdl";
+
+/// Returns the platform's per-user app data directory joined with `SoundClip`
+/// (e.g. `%LOCALAPPDATA%\SoundClip` on Windows, `~/.local/share/SoundClip` on
+/// Linux, `~/Library/Application Support/SoundClip` on macOS).
 pub fn app_data_dir() -> PathBuf {
     dirs::data_local_dir()
         .unwrap_or_else(|| PathBuf::from("."))
         .join("SoundClip")
 }
 
-/// Returns `%LOCALAPPDATA%\SoundClip\bin`.
+/// Returns the app data directory's `bin` subfolder.
 pub fn bin_dir() -> PathBuf {
     app_data_dir().join("bin")
 }
 
 /// Full path to the yt-dlp binary.
 pub fn ytdlp_path() -> PathBuf {
-    bin_dir().join("yt-dlp.exe")
+    bin_dir().join(BINARY_NAME)
 }
 
-/// Check whether yt-dlp.exe exists in the expected location.
+/// Check whether yt-dlp exists in the expected location.
 pub fn is_ytdlp_installed() -> bool {
     ytdlp_path().is_file()
 }
 
 /// Check whether ffmpeg is reachable — either in the bin dir or on PATH.
 pub fn is_ffmpeg_installed() -> bool {
-    let bin = bin_dir().join("ffmpeg.exe");
+    let bin = bin_dir().join(FFMPEG_BINARY_NAME);
     if bin.is_file() {
         return true;
     }
-    Command::new("ffmpeg")
+    Command::new(FFMPEG_BINARY_NAME)
         .arg("-version")
         .stdout(std::process::Stdio::null())
         .stderr(std::process::Stdio::null())
@@ -38,6 +64,25 @@ pub fn is_ffmpeg_installed() -> bool {
         .unwrap_or(false)
 }
 
+/// Full path to the spotDL binary.
+pub fn spotdl_path() -> PathBuf {
+    bin_dir().join(SPOTDL_BINARY_NAME)
+}
+
+/// Check whether spotDL is reachable — either in the bin dir or on PATH.
+pub fn is_spotdl_installed() -> bool {
+    if spotdl_path().is_file() {
+        return true;
+    }
+    Command::new(SPOTDL_BINARY_NAME)
+        .arg("--version")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
 /// Return the first available JS runtime for yt-dlp's YouTube extractor.
 /// Priority: node > deno > bun.
 pub fn ytdlp_js_runtime() -> Option<String> {
@@ -56,19 +101,116 @@ pub fn ytdlp_js_runtime() -> Option<String> {
     None
 }
 
-/// Kill an entire process tree on Windows using `taskkill /T /F /PID`.
+/// Apply the platform-specific spawn settings a managed child process needs:
+/// suppress the console window on Windows, and on Unix make the child the
+/// leader of its own process group so [`kill_process_tree`] can reach the
+/// whole tree (yt-dlp's ffmpeg/ffprobe children included) with one call.
+pub fn prepare_child(cmd: &mut Command) {
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+}
+
+/// Same as [`prepare_child`] but for a `tokio::process::Command`.
+pub fn prepare_tokio_child(cmd: &mut tokio::process::Command) {
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+}
+
+/// Kill an entire process tree: `taskkill /T /F /PID` on Windows, or a
+/// negative-PID `kill` targeting the whole process group on Linux/macOS.
+/// Relies on the child having been spawned via [`prepare_child`] /
+/// [`prepare_tokio_child`] so it is its own group leader.
 pub fn kill_process_tree(pid: u32) {
-    let _ = Command::new("taskkill")
-        .args(["/T", "/F", "/PID", &pid.to_string()])
-        .stdout(std::process::Stdio::null())
-        .stderr(std::process::Stdio::null())
-        .status();
+    #[cfg(windows)]
+    {
+        let _ = Command::new("taskkill")
+            .args(["/T", "/F", "/PID", &pid.to_string()])
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status();
+    }
+    #[cfg(unix)]
+    {
+        // SAFETY: `kill` with a negative pid signals the whole process group;
+        // it has no memory-safety preconditions beyond a valid signal number.
+        unsafe {
+            libc::kill(-(pid as i32), libc::SIGKILL);
+        }
+    }
+}
+
+/// Structured progress parsed from a yt-dlp `--newline` progress line, e.g.
+/// `[download]  45.2% of ~10.00MiB at    2.30MiB/s ETA 00:07 (item 2 of 5)`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DownloadProgress {
+    pub percent: f64,
+    pub total_size: Option<String>,
+    pub speed: Option<String>,
+    pub eta: Option<String>,
+    pub item_index: Option<u32>,
+    pub item_count: Option<u32>,
 }
 
-/// Parse a yt-dlp progress line like `[download]  45.2% of ~10MiB ...` and
-/// return the percentage as a float, or `None` if the line is not a progress line.
-pub fn parse_progress(line: &str) -> Option<f64> {
-    let re = regex::Regex::new(r"\[download\]\s+([\d.]+)%").ok()?;
+/// Parse a yt-dlp `--newline` progress line into a [`DownloadProgress`].
+/// Returns `None` only when no percent token is found; every other field is
+/// optional so a partial line still yields useful data.
+pub fn parse_progress(line: &str) -> Option<DownloadProgress> {
+    let re = regex::Regex::new(
+        r"(?x)
+        \[download\]\s+(?P<percent>[\d.]+)%
+        (?:\s+of\s+~?\s*(?P<total>[\d.]+\S*))?
+        (?:\s+at\s+(?P<speed>[\d.]+\S*/s|Unknown\ speed))?
+        (?:\s+ETA\s+(?P<eta>[\d:]+|Unknown\ ETA))?
+        (?:.*\(item\s+(?P<item>\d+)\s+of\s+(?P<count>\d+)\))?
+        ",
+    )
+    .ok()?;
     let caps = re.captures(line)?;
-    caps.get(1)?.as_str().parse::<f64>().ok()
+    let percent = caps.name("percent")?.as_str().parse::<f64>().ok()?;
+
+    Some(DownloadProgress {
+        percent,
+        total_size: caps.name("total").map(|m| m.as_str().to_string()),
+        speed: caps.name("speed").map(|m| m.as_str().to_string()),
+        eta: caps.name("eta").map(|m| m.as_str().to_string()),
+        item_index: caps.name("item").and_then(|m| m.as_str().parse().ok()),
+        item_count: caps.name("count").and_then(|m| m.as_str().parse().ok()),
+    })
+}
+
+/// Parse a spotDL progress line, e.g. `Downloading "Song Name": 45%`. spotDL's
+/// tqdm-style bar doesn't report size/speed/ETA or playlist position the way
+/// yt-dlp's `--newline` output does, so only `percent` is ever populated.
+/// Anchored on the `Downloading "..."` prefix, mirroring how [`parse_progress`]
+/// anchors on `[download]`, so stray `NN%` substrings elsewhere in spotDL's
+/// output (errors, URLs, version banners) aren't misread as progress.
+pub fn parse_spotdl_progress(line: &str) -> Option<DownloadProgress> {
+    let re = regex::Regex::new(r#"^Downloading\s+".*?":\s*(?P<percent>[\d.]+)%"#).ok()?;
+    let caps = re.captures(line.trim())?;
+    let percent = caps.name("percent")?.as_str().parse::<f64>().ok()?;
+
+    Some(DownloadProgress {
+        percent,
+        total_size: None,
+        speed: None,
+        eta: None,
+        item_index: None,
+        item_count: None,
+    })
 }